@@ -10,47 +10,68 @@ pub enum LiteralValue {
     Nil,
 }
 
-#[allow(dead_code)]
-fn unwrap_as_f64(literal: Option<scanner::LiteralValue>) -> f64 {
+fn unwrap_as_f64(literal: Option<scanner::LiteralValue>) -> Result<f64, String> {
     match literal {
-        Some(scanner::LiteralValue::IntValue(x)) => x as f64,
-        Some(scanner::LiteralValue::FValue(x)) => x,
-        _ => panic!("Could not unwrap as f32"),
+        Some(scanner::LiteralValue::IntValue(x)) => Ok(x as f64),
+        Some(scanner::LiteralValue::FValue(x)) => Ok(x),
+        other => Err(format!("Could not unwrap as f64: {:?}", other)),
     }
 }
 
-#[allow(dead_code)]
-fn unwrap_as_string(literal: Option<scanner::LiteralValue>) -> String {
+fn unwrap_as_string(literal: Option<scanner::LiteralValue>) -> Result<String, String> {
     match literal {
-        Some(scanner::LiteralValue::StringValue(s)) => s.clone(),
-        Some(scanner::LiteralValue::IdentifierVal(s)) => s.clone(),
-        _ => panic!("Could not unwrap as string"),
+        Some(scanner::LiteralValue::StringValue(s)) => Ok(s),
+        Some(scanner::LiteralValue::IdentifierVal(s)) => Ok(s),
+        other => Err(format!("Could not unwrap as string: {:?}", other)),
     }
 }
 
-impl LiteralValue {
-    pub fn to_string(&self) -> String {
+impl std::fmt::Display for LiteralValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
-            LiteralValue::Number(x) => x.to_string(),
-            LiteralValue::StringValue(x) => x.clone(),
-            LiteralValue::True => "true".to_string(),
-            LiteralValue::False => "false".to_string(),
-            LiteralValue::Nil => "nil".to_string(),
+            LiteralValue::Number(x) => write!(f, "{}", x),
+            LiteralValue::StringValue(x) => write!(f, "{}", x),
+            LiteralValue::True => write!(f, "true"),
+            LiteralValue::False => write!(f, "false"),
+            LiteralValue::Nil => write!(f, "nil"),
         }
     }
+}
 
-    pub fn from_token(token: Token) -> Self {
+impl LiteralValue {
+    pub fn from_token(token: Token) -> Result<Self, String> {
         match token.token_type {
-            TokenType::Number => Self::Number(unwrap_as_f64(token.literal)),
-            TokenType::StringLit => Self::StringValue(unwrap_as_string(token.literal)),
-            TokenType::False => Self::False,
-            TokenType::True => Self::True,
-            TokenType::Nil => Self::Nil,
-            _ => panic!("Could not create LiteralValue from {:?}", token),
+            TokenType::Number => Ok(Self::Number(unwrap_as_f64(token.literal)?)),
+            TokenType::StringLit => Ok(Self::StringValue(unwrap_as_string(token.literal)?)),
+            TokenType::False => Ok(Self::False),
+            TokenType::True => Ok(Self::True),
+            TokenType::Nil => Ok(Self::Nil),
+            _ => Err(format!(
+                "Could not create LiteralValue from {} at line {}",
+                token, token.line_number
+            )),
         }
     }
 }
 
+#[allow(dead_code)]
+pub struct Parameter {
+    pub name: Token,
+    pub default: Option<Expr>,
+}
+
+#[allow(dead_code)]
+pub struct NamedArgument {
+    pub name: Token,
+    pub value: Expr,
+}
+
+#[allow(dead_code)]
+pub enum MapEntry {
+    Pair(Expr, Expr),
+    Spread(Expr),
+}
+
 #[allow(dead_code)]
 pub enum Expr {
     Binary {
@@ -68,34 +89,162 @@ pub enum Expr {
         operator: Token,
         right: Box<Expr>,
     },
+    Range {
+        start: Box<Expr>,
+        operator: Token,
+        end: Box<Expr>,
+        step: Option<Box<Expr>>,
+    },
+    Spread {
+        operator: Token,
+        expression: Box<Expr>,
+    },
+    Variable {
+        name: Token,
+    },
+    Lambda {
+        params: Vec<Parameter>,
+        rest: Option<Token>,
+        body: Box<Expr>,
+    },
+    Call {
+        callee: Box<Expr>,
+        paren: Token,
+        arguments: Vec<Expr>,
+        named: Vec<NamedArgument>,
+    },
+    Get {
+        object: Box<Expr>,
+        name: Token,
+    },
+    List {
+        elements: Vec<Expr>,
+    },
+    Map {
+        brace: Token,
+        entries: Vec<MapEntry>,
+    },
+    Index {
+        object: Box<Expr>,
+        bracket: Token,
+        index: Box<Expr>,
+    },
+    Slice {
+        object: Box<Expr>,
+        bracket: Token,
+        start: Option<Box<Expr>>,
+        end: Option<Box<Expr>>,
+    },
+    IndexSet {
+        object: Box<Expr>,
+        bracket: Token,
+        index: Box<Expr>,
+        value: Box<Expr>,
+    },
 }
 
-#[allow(dead_code)]
-impl Expr {
-    pub fn to_string(&self) -> String {
+impl std::fmt::Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Expr::Binary {
                 left,
                 operator,
                 right,
-            } => format!(
-                "({} {} {})",
-                operator.lexeme,
-                left.to_string(),
-                right.to_string()
-            ),
-            Expr::Grouping { expression } => format!("(group {})", (*expression).to_string()),
-            Expr::Literal { value } => format!("{}", value.to_string()),
-            Expr::Unary { operator, right } => {
-                let operator_str = operator.lexeme.clone();
-                let right_str = (*right).to_string();
-                format!("({} {})", operator_str, right_str)
+            } => write!(f, "({} {} {})", operator.lexeme, left, right),
+            Expr::Grouping { expression } => write!(f, "(group {})", expression),
+            Expr::Literal { value } => write!(f, "{}", value),
+            Expr::Unary { operator, right } => write!(f, "({} {})", operator.lexeme, right),
+            Expr::Range {
+                start,
+                operator,
+                end,
+                step,
+            } => match step {
+                Some(step) => write!(f, "({} {} {} {})", operator.lexeme, start, end, step),
+                None => write!(f, "({} {} {})", operator.lexeme, start, end),
+            },
+            Expr::Spread {
+                operator,
+                expression,
+            } => write!(f, "({} {})", operator.lexeme, expression),
+            Expr::Variable { name } => write!(f, "{}", name.lexeme),
+            Expr::Lambda { params, rest, body } => {
+                let mut names: Vec<String> = params
+                    .iter()
+                    .map(|p| match &p.default {
+                        Some(default) => format!("{}={}", p.name.lexeme, default),
+                        None => p.name.lexeme.clone(),
+                    })
+                    .collect();
+                if let Some(rest) = rest {
+                    names.push(format!("...{}", rest.lexeme));
+                }
+                write!(f, "(lambda ({}) {})", names.join(" "), body)
+            }
+            Expr::Call {
+                callee,
+                paren: _,
+                arguments,
+                named,
+            } => {
+                write!(f, "(call {}", callee)?;
+                for argument in arguments {
+                    write!(f, " {}", argument)?;
+                }
+                for argument in named {
+                    write!(f, " {}: {}", argument.name.lexeme, argument.value)?;
+                }
+                write!(f, ")")
+            }
+            Expr::Get { object, name } => write!(f, "(get {} {})", object, name.lexeme),
+            Expr::List { elements } => {
+                write!(f, "(list")?;
+                for element in elements {
+                    write!(f, " {}", element)?;
+                }
+                write!(f, ")")
+            }
+            Expr::Map { brace: _, entries } => {
+                write!(f, "(map")?;
+                for entry in entries {
+                    match entry {
+                        MapEntry::Pair(key, value) => write!(f, " ({} {})", key, value)?,
+                        MapEntry::Spread(spread) => write!(f, " {}", spread)?,
+                    }
+                }
+                write!(f, ")")
             }
+            Expr::Index {
+                object,
+                bracket: _,
+                index,
+            } => write!(f, "(index {} {})", object, index),
+            Expr::Slice {
+                object,
+                bracket: _,
+                start,
+                end,
+            } => {
+                let bound = |b: &Option<Box<Expr>>| match b {
+                    Some(expr) => expr.to_string(),
+                    None => "_".to_string(),
+                };
+                write!(f, "(slice {} {} {})", object, bound(start), bound(end))
+            }
+            Expr::IndexSet {
+                object,
+                bracket: _,
+                index,
+                value,
+            } => write!(f, "(= (index {} {}) {})", object, index, value),
         }
     }
+}
 
+#[allow(dead_code)]
+impl Expr {
     pub fn print(&self) {
-        println!("{}", self.to_string());
+        println!("{}", self);
     }
 }
 
@@ -138,4 +287,35 @@ mod tests {
         let result = ast.to_string();
         assert_eq!(result, "(* (- 123) (group 45.67))");
     }
+
+    #[test]
+    fn literal_from_mismatched_token() {
+        let number_without_value = Token::new(TokenType::Number, "1".to_string(), None, 3);
+        assert!(LiteralValue::from_token(number_without_value).is_err());
+
+        let string_with_number = Token::new(
+            TokenType::StringLit,
+            "\"a\"".to_string(),
+            Some(scanner::LiteralValue::FValue(1.0)),
+            3,
+        );
+        assert!(LiteralValue::from_token(string_with_number).is_err());
+
+        let not_a_literal = Token::new(TokenType::Plus, "+".to_string(), None, 3);
+        match LiteralValue::from_token(not_a_literal) {
+            Ok(_) => panic!("should have failed"),
+            Err(msg) => assert!(msg.contains("line 3")),
+        }
+
+        let int_value = Token::new(
+            TokenType::Number,
+            "7".to_string(),
+            Some(scanner::LiteralValue::IntValue(7)),
+            1,
+        );
+        assert_eq!(
+            LiteralValue::from_token(int_value).unwrap().to_string(),
+            "7"
+        );
+    }
 }
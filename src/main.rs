@@ -39,8 +39,8 @@ fn run_prompt() -> Result<(), String> {
 
 fn run_file(path: &str) -> Result<(), String> {
     match fs::read_to_string(path) {
-        Err(msg) => return Err(msg.to_string()),
-        Ok(contents) => return run(&contents),
+        Err(msg) => Err(msg.to_string()),
+        Ok(contents) => run(&contents),
     }
 }
 
@@ -49,8 +49,8 @@ fn run(contents: &str) -> Result<(), String> {
     let tokens = scanner.scan_tokens()?;
     let mut parser = Parser::new(tokens);
     let expr = parser.parse()?;
-    println!("{}", expr.to_string());
-    return Ok(());
+    println!("{}", expr);
+    Ok(())
 }
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -1,11 +1,24 @@
+use std::collections::HashSet;
+
 use crate::scanner::{Token, TokenType, TokenType::*};
 
-use crate::expr::{Expr, Expr::*, LiteralValue};
+use crate::expr::{Expr, Expr::*, LiteralValue, MapEntry, NamedArgument, Parameter};
+
+// Bounds the recursive descent so hostile input cannot overflow the native
+// stack; 64 levels fit comfortably in a 2 MiB thread stack in debug builds.
+const MAX_NESTING: usize = 64;
+
+// Bounds the height of the tree itself. Left-associative loops build chains
+// without recursing, but printing and dropping an Expr still recurse once per
+// level, at roughly 2.5 KiB per level in debug builds.
+const MAX_AST_DEPTH: usize = 256;
 
 #[allow(dead_code)]
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    depth: usize,
+    ast_depth: usize,
 }
 
 #[allow(unused_macros)]
@@ -26,8 +39,10 @@ macro_rules! match_tokens {
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
         Self {
-            tokens: tokens,
+            tokens,
             current: 0,
+            depth: 0,
+            ast_depth: 0,
         }
     }
 
@@ -36,17 +51,79 @@ impl Parser {
     }
 
     fn expression(&mut self) -> Result<Expr, String> {
-        self.equality()
+        self.nested(|parser| parser.assignment())
+    }
+
+    fn nested<F>(&mut self, rule: F) -> Result<Expr, String>
+    where
+        F: FnOnce(&mut Self) -> Result<Expr, String>,
+    {
+        if self.depth >= MAX_NESTING {
+            return Err(format!(
+                "Expression nested too deeply at line {}",
+                self.peek().line_number
+            ));
+        }
+        self.depth += 1;
+        let result = rule(self);
+        self.depth -= 1;
+
+        result
+    }
+
+    // Records the height of a node whose tallest child is `children` high.
+    // Every rule leaves the height of the expression it returns in ast_depth.
+    fn wrap(&mut self, children: usize) -> Result<(), String> {
+        if children >= MAX_AST_DEPTH {
+            return Err(format!(
+                "Expression nested too deeply at line {}",
+                self.previous().line_number
+            ));
+        }
+        self.ast_depth = children + 1;
+
+        Ok(())
+    }
+
+    fn assignment(&mut self) -> Result<Expr, String> {
+        let expr = self.equality()?;
+
+        if self.match_token(Equal) {
+            let equals = self.previous();
+            let target_depth = self.ast_depth;
+            let value = self.expression()?;
+            self.wrap(target_depth.max(self.ast_depth))?;
+            match expr {
+                Index {
+                    object,
+                    bracket,
+                    index,
+                } => Ok(IndexSet {
+                    object,
+                    bracket,
+                    index,
+                    value: Box::from(value),
+                }),
+                _ => Err(format!(
+                    "Invalid assignment target at line {}",
+                    equals.line_number
+                )),
+            }
+        } else {
+            Ok(expr)
+        }
     }
 
     fn equality(&mut self) -> Result<Expr, String> {
         let mut expr = self.comparison()?;
         while self.match_tokens(&[BangEqual, EqualEqual]) {
             let operator = self.previous();
+            let left_depth = self.ast_depth;
             let rhs = self.comparison()?;
+            self.wrap(left_depth.max(self.ast_depth))?;
             expr = Binary {
                 left: Box::from(expr),
-                operator: operator,
+                operator,
                 right: Box::from(rhs),
             };
         }
@@ -55,11 +132,13 @@ impl Parser {
     }
 
     fn comparison(&mut self) -> Result<Expr, String> {
-        let mut expr = self.term()?;
+        let mut expr = self.range()?;
 
-        while self.match_tokens(&[Greater, GreaterEqual, Less, LessEqual]) {
+        while self.match_tokens(&[Greater, GreaterEqual, Less, LessEqual, In]) {
             let op = self.previous();
-            let rhs = self.term()?;
+            let left_depth = self.ast_depth;
+            let rhs = self.range()?;
+            self.wrap(left_depth.max(self.ast_depth))?;
             expr = Binary {
                 left: Box::from(expr),
                 operator: op,
@@ -70,12 +149,48 @@ impl Parser {
         Ok(expr)
     }
 
+    fn range(&mut self) -> Result<Expr, String> {
+        let expr = self.term()?;
+
+        if self.match_tokens(&[DotDot, DotDotEqual]) {
+            let operator = self.previous();
+            let mut children_depth = self.ast_depth;
+            let end = self.term()?;
+            children_depth = children_depth.max(self.ast_depth);
+            let step = if self.check(Identifier) && self.peek().lexeme == "step" {
+                self.advance();
+                let step = self.term()?;
+                children_depth = children_depth.max(self.ast_depth);
+                Some(Box::from(step))
+            } else {
+                None
+            };
+            self.wrap(children_depth)?;
+            if self.check(DotDot) || self.check(DotDotEqual) {
+                return Err(format!(
+                    "Ranges cannot be chained at line {}",
+                    operator.line_number
+                ));
+            }
+            Ok(Range {
+                start: Box::from(expr),
+                operator,
+                end: Box::from(end),
+                step,
+            })
+        } else {
+            Ok(expr)
+        }
+    }
+
     fn term(&mut self) -> Result<Expr, String> {
         let mut expr = self.factor()?;
 
         while self.match_tokens(&[Minus, Plus]) {
             let op = self.previous();
+            let left_depth = self.ast_depth;
             let rhs = self.factor()?;
+            self.wrap(left_depth.max(self.ast_depth))?;
             expr = Binary {
                 left: Box::from(expr),
                 operator: op,
@@ -90,7 +205,9 @@ impl Parser {
         let mut expr = self.unary()?;
         while self.match_tokens(&[Slash, Star]) {
             let op = self.previous();
+            let left_depth = self.ast_depth;
             let rhs = self.unary()?;
+            self.wrap(left_depth.max(self.ast_depth))?;
             expr = Binary {
                 left: Box::from(expr),
                 operator: op,
@@ -104,41 +221,330 @@ impl Parser {
     fn unary(&mut self) -> Result<Expr, String> {
         if self.match_tokens(&[Bang, Minus]) {
             let op = self.previous();
-            let rhs = self.unary()?;
+            let rhs = self.nested(|parser| parser.unary())?;
+            self.wrap(self.ast_depth)?;
             Ok(Unary {
                 operator: op,
                 right: Box::from(rhs),
             })
         } else {
-            self.primary()
+            self.call()
+        }
+    }
+
+    fn call(&mut self) -> Result<Expr, String> {
+        let mut expr = self.primary()?;
+
+        loop {
+            if self.match_token(LeftParen) {
+                expr = self.finish_call(expr)?;
+            } else if self.match_token(LeftBracket) {
+                expr = self.finish_index(expr)?;
+            } else if self.match_token(Dot) {
+                let name = self.peek();
+                self.consume(Identifier, "Expected property name after '.'")?;
+                self.wrap(self.ast_depth)?;
+                expr = Get {
+                    object: Box::from(expr),
+                    name,
+                };
+            } else {
+                break;
+            }
+        }
+
+        Ok(expr)
+    }
+
+    fn finish_call(&mut self, callee: Expr) -> Result<Expr, String> {
+        let mut children_depth = self.ast_depth;
+        let mut arguments = vec![];
+        let mut named: Vec<NamedArgument> = vec![];
+        let mut named_seen: HashSet<String> = HashSet::new();
+        if !self.check(RightParen) {
+            loop {
+                if !self.starts_argument() {
+                    return Err(format!(
+                        "Expected argument at line {}",
+                        self.peek().line_number
+                    ));
+                }
+                if self.check(Identifier) && self.check_next(Colon) {
+                    let name = self.advance();
+                    self.advance();
+                    if !named_seen.insert(name.lexeme.clone()) {
+                        return Err(format!(
+                            "Duplicate named argument at line {}: {}",
+                            name.line_number, name.lexeme
+                        ));
+                    }
+                    let value = self.expression()?;
+                    children_depth = children_depth.max(self.ast_depth);
+                    named.push(NamedArgument { name, value });
+                } else if !named.is_empty() {
+                    return Err(format!(
+                        "Positional argument after named arguments at line {}",
+                        self.peek().line_number
+                    ));
+                } else {
+                    arguments.push(self.spread_or_expression()?);
+                    children_depth = children_depth.max(self.ast_depth);
+                }
+                if !self.match_token(Comma) {
+                    break;
+                }
+            }
+        }
+        self.consume(RightParen, "Expected ')' after arguments")?;
+        self.wrap(children_depth)?;
+
+        Ok(Call {
+            callee: Box::from(callee),
+            paren: self.previous(),
+            arguments,
+            named,
+        })
+    }
+
+    fn starts_argument(&mut self) -> bool {
+        matches!(
+            self.peek().token_type,
+            LeftParen
+                | LeftBracket
+                | LeftBrace
+                | Bang
+                | Minus
+                | Ellipsis
+                | Identifier
+                | StringLit
+                | Number
+                | False
+                | True
+                | Nil
+        )
+    }
+
+    fn spread_or_expression(&mut self) -> Result<Expr, String> {
+        if self.match_token(Ellipsis) {
+            let operator = self.previous();
+            let expression = self.expression()?;
+            self.wrap(self.ast_depth)?;
+            Ok(Spread {
+                operator,
+                expression: Box::from(expression),
+            })
+        } else {
+            self.expression()
         }
     }
 
+    fn finish_index(&mut self, object: Expr) -> Result<Expr, String> {
+        let bracket = self.previous();
+        let mut children_depth = self.ast_depth;
+        let start = if self.check(Colon) {
+            None
+        } else {
+            let start = self.expression()?;
+            children_depth = children_depth.max(self.ast_depth);
+            Some(Box::from(start))
+        };
+
+        let expr = if self.match_token(Colon) {
+            let end = if self.check(RightBracket) {
+                None
+            } else {
+                let end = self.expression()?;
+                children_depth = children_depth.max(self.ast_depth);
+                Some(Box::from(end))
+            };
+            Slice {
+                object: Box::from(object),
+                bracket,
+                start,
+                end,
+            }
+        } else {
+            match start {
+                Some(index) => Index {
+                    object: Box::from(object),
+                    bracket,
+                    index,
+                },
+                None => {
+                    return Err(format!(
+                        "Expected index expression at line {}",
+                        bracket.line_number
+                    ))
+                }
+            }
+        };
+        self.consume(RightBracket, "Expected ']' after index")?;
+        self.wrap(children_depth)?;
+
+        Ok(expr)
+    }
+
     fn primary(&mut self) -> Result<Expr, String> {
         let token = self.peek();
 
-        let result;
-        match token.token_type {
+        let result = match token.token_type {
+            LeftParen if self.is_lambda_start() => self.lambda()?,
             LeftParen => {
                 self.advance();
                 let expr = self.expression()?;
                 self.consume(RightParen, "Expected ')'")?;
-                result = Grouping {
+                self.wrap(self.ast_depth)?;
+                Grouping {
                     expression: Box::from(expr),
-                };
+                }
             }
             False | True | Nil | Number | StringLit => {
                 self.advance();
-                result = Literal {
-                    value: LiteralValue::from_token(token),
+                self.wrap(0)?;
+                Literal {
+                    value: LiteralValue::from_token(token)?,
                 }
             }
+            Identifier => {
+                self.advance();
+                self.wrap(0)?;
+                Variable { name: token }
+            }
+            LeftBracket => self.list()?,
+            LeftBrace => self.map()?,
             _ => return Err("Expected expression".to_string()),
-        }
+        };
 
         Ok(result)
     }
 
+    fn list(&mut self) -> Result<Expr, String> {
+        self.consume(LeftBracket, "Expected '[' before list elements")?;
+        let mut elements = vec![];
+        let mut children_depth = 0;
+        if !self.check(RightBracket) {
+            loop {
+                elements.push(self.spread_or_expression()?);
+                children_depth = children_depth.max(self.ast_depth);
+                if !self.match_token(Comma) {
+                    break;
+                }
+            }
+        }
+        self.consume(RightBracket, "Expected ']' after list elements")?;
+        self.wrap(children_depth)?;
+
+        Ok(List { elements })
+    }
+
+    fn map(&mut self) -> Result<Expr, String> {
+        let brace = self.peek();
+        self.consume(LeftBrace, "Expected '{' before map entries")?;
+        let mut entries = vec![];
+        let mut children_depth = 0;
+        if !self.check(RightBrace) {
+            loop {
+                if self.check(Ellipsis) {
+                    entries.push(MapEntry::Spread(self.spread_or_expression()?));
+                    children_depth = children_depth.max(self.ast_depth);
+                } else {
+                    let key = self.expression()?;
+                    children_depth = children_depth.max(self.ast_depth);
+                    self.consume(Colon, "Expected ':' after map key")?;
+                    let value = self.expression()?;
+                    children_depth = children_depth.max(self.ast_depth);
+                    entries.push(MapEntry::Pair(key, value));
+                }
+                if !self.match_token(Comma) {
+                    break;
+                }
+            }
+        }
+        self.consume(RightBrace, "Expected '}' after map entries")?;
+        self.wrap(children_depth)?;
+
+        Ok(Map { brace, entries })
+    }
+
+    fn lambda(&mut self) -> Result<Expr, String> {
+        self.consume(LeftParen, "Expected '(' before lambda parameters")?;
+        let mut params: Vec<Parameter> = vec![];
+        let mut param_names: HashSet<String> = HashSet::new();
+        let mut rest = None;
+        let mut seen_default = false;
+        let mut children_depth = 0;
+        if !self.check(RightParen) {
+            loop {
+                if self.match_token(Ellipsis) {
+                    let name = self.peek();
+                    self.consume(Identifier, "Expected rest parameter name after '...'")?;
+                    Self::check_duplicate_parameter(&mut param_names, &name)?;
+                    rest = Some(name);
+                    break;
+                }
+                let name = self.peek();
+                self.consume(Identifier, "Expected parameter name")?;
+                Self::check_duplicate_parameter(&mut param_names, &name)?;
+                let default = if self.match_token(Equal) {
+                    let default = self.expression()?;
+                    children_depth = children_depth.max(self.ast_depth);
+                    seen_default = true;
+                    Some(default)
+                } else if seen_default {
+                    return Err(format!(
+                        "Parameter without a default after defaulted ones at line {}: {}",
+                        name.line_number, name.lexeme
+                    ));
+                } else {
+                    None
+                };
+                params.push(Parameter { name, default });
+                if !self.match_token(Comma) {
+                    break;
+                }
+            }
+        }
+        self.consume(RightParen, "Expected ')' after lambda parameters")?;
+        self.consume(Arrow, "Expected '=>' after lambda parameters")?;
+        let body = self.expression()?;
+        self.wrap(children_depth.max(self.ast_depth))?;
+
+        Ok(Lambda {
+            params,
+            rest,
+            body: Box::from(body),
+        })
+    }
+
+    fn check_duplicate_parameter(seen: &mut HashSet<String>, name: &Token) -> Result<(), String> {
+        if !seen.insert(name.lexeme.clone()) {
+            Err(format!(
+                "Duplicate parameter name at line {}: {}",
+                name.line_number, name.lexeme
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn is_lambda_start(&self) -> bool {
+        let mut depth = 0;
+        for (i, token) in self.tokens.iter().enumerate().skip(self.current) {
+            match token.token_type {
+                LeftParen => depth += 1,
+                RightParen => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return self.tokens.get(i + 1).map(|t| t.token_type) == Some(Arrow);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        false
+    }
+
     fn consume(&mut self, token_type: TokenType, msg: &str) -> Result<(), String> {
         let token = self.peek();
         if token.token_type == token_type {
@@ -149,6 +555,14 @@ impl Parser {
         }
     }
 
+    fn check(&mut self, typ: TokenType) -> bool {
+        self.peek().token_type == typ
+    }
+
+    fn check_next(&self, typ: TokenType) -> bool {
+        self.tokens.get(self.current + 1).map(|t| t.token_type) == Some(typ)
+    }
+
     fn match_token(&mut self, typ: TokenType) -> bool {
         if self.is_at_end() {
             false
@@ -181,11 +595,22 @@ impl Parser {
     }
 
     fn peek(&mut self) -> Token {
-        self.tokens[self.current].clone()
+        match self.tokens.get(self.current) {
+            Some(token) => token.clone(),
+            None => self.eof(),
+        }
     }
 
     fn previous(&mut self) -> Token {
-        self.tokens[self.current - 1].clone()
+        match self.current.checked_sub(1).and_then(|i| self.tokens.get(i)) {
+            Some(token) => token.clone(),
+            None => self.eof(),
+        }
+    }
+
+    fn eof(&self) -> Token {
+        let line_number = self.tokens.last().map_or(0, |t| t.line_number);
+        Token::new(Eof, "".to_string(), None, line_number)
     }
 
     fn is_at_end(&mut self) -> bool {
@@ -200,7 +625,8 @@ impl Parser {
             }
 
             match self.peek().token_type {
-                Class | Fun | Var | For | If | While | Print | Return => return,
+                Class | Fun | Var | Const | For | If | While | Print | Return | Break
+                | Continue | Match | Do | Defer | Throw | Try | Import | Export => return,
                 _ => (),
             }
 
@@ -217,8 +643,6 @@ mod tests {
 
     #[test]
     fn test_addition() {
-         #[test]
-    fn test_eq_with_paren() {
         let source = "1 + 2;";
         let mut scanner = Scanner::new(source);
         let tokens = scanner.scan_tokens().unwrap();
@@ -252,4 +676,354 @@ mod tests {
 
         assert_eq!(string_expr, "(== 1 (group (+ 2 2)))");
     }
+
+    #[test]
+    fn test_lambda() {
+        let source = "(a, b) => a + b";
+        let mut scanner = Scanner::new(source);
+        let tokens = scanner.scan_tokens().unwrap();
+        let mut parser = Parser::new(tokens);
+        let parsed_expr = parser.parse().unwrap();
+        let string_expr = parsed_expr.to_string();
+
+        assert_eq!(string_expr, "(lambda (a b) (+ a b))");
+    }
+
+    #[test]
+    fn test_lambda_no_params_vs_grouping() {
+        let source = "(() => 1) == (x)";
+        let mut scanner = Scanner::new(source);
+        let tokens = scanner.scan_tokens().unwrap();
+        let mut parser = Parser::new(tokens);
+        let parsed_expr = parser.parse().unwrap();
+        let string_expr = parsed_expr.to_string();
+
+        assert_eq!(string_expr, "(== (group (lambda () 1)) (group x))");
+    }
+
+    #[test]
+    fn test_lambda_defaults_and_rest() {
+        let source = "(fmt, width = 2, ...args) => fmt";
+        let mut scanner = Scanner::new(source);
+        let tokens = scanner.scan_tokens().unwrap();
+        let mut parser = Parser::new(tokens);
+        let parsed_expr = parser.parse().unwrap();
+        let string_expr = parsed_expr.to_string();
+
+        assert_eq!(string_expr, "(lambda (fmt width=2 ...args) fmt)");
+    }
+
+    #[test]
+    fn test_lambda_default_ordering() {
+        let source = "(a = 1, b) => a";
+        let mut scanner = Scanner::new(source);
+        let tokens = scanner.scan_tokens().unwrap();
+        let mut parser = Parser::new(tokens);
+
+        assert!(parser.parse().is_err());
+    }
+
+    #[test]
+    fn test_lambda_duplicate_parameters() {
+        for source in ["(a, a) => a", "(a, b = 1, ...a) => a"] {
+            let mut scanner = Scanner::new(source);
+            let tokens = scanner.scan_tokens().unwrap();
+            let mut parser = Parser::new(tokens);
+
+            match parser.parse() {
+                Ok(_) => panic!("should have failed"),
+                Err(msg) => assert!(msg.contains("line 1: a")),
+            }
+        }
+    }
+
+    #[test]
+    fn test_call_with_named_arguments() {
+        let source = "f(1, b: 3, a: 1 + 2)(x)";
+        let mut scanner = Scanner::new(source);
+        let tokens = scanner.scan_tokens().unwrap();
+        let mut parser = Parser::new(tokens);
+        let parsed_expr = parser.parse().unwrap();
+        let string_expr = parsed_expr.to_string();
+
+        assert_eq!(string_expr, "(call (call f 1 b: 3 a: (+ 1 2)) x)");
+    }
+
+    #[test]
+    fn test_call_invalid_named_arguments() {
+        let cases = [
+            (
+                "f(a: 1, 2)",
+                "Positional argument after named arguments at line 1",
+            ),
+            ("f(a: 1, a: 2)", "Duplicate named argument at line 1: a"),
+            ("f(a: 1,)", "Expected argument at line 1"),
+            ("f(a: 1, ;)", "Expected argument at line 1"),
+            ("f(1,)", "Expected argument at line 1"),
+        ];
+        for (source, expected) in cases {
+            let mut scanner = Scanner::new(source);
+            let tokens = scanner.scan_tokens().unwrap();
+            let mut parser = Parser::new(tokens);
+
+            match parser.parse() {
+                Ok(_) => panic!("should have failed: {}", source),
+                Err(msg) => assert_eq!(msg, expected),
+            }
+        }
+    }
+
+    #[test]
+    fn test_list_literal_and_index() {
+        let source = "[1, 2 + 3, []][-1]";
+        let mut scanner = Scanner::new(source);
+        let tokens = scanner.scan_tokens().unwrap();
+        let mut parser = Parser::new(tokens);
+        let parsed_expr = parser.parse().unwrap();
+        let string_expr = parsed_expr.to_string();
+
+        assert_eq!(string_expr, "(index (list 1 (+ 2 3) (list)) (- 1))");
+    }
+
+    #[test]
+    fn test_slices() {
+        let cases = [
+            ("xs[1:3]", "(slice xs 1 3)"),
+            ("xs[:n]", "(slice xs _ n)"),
+            ("xs[1:]", "(slice xs 1 _)"),
+            ("xs[:]", "(slice xs _ _)"),
+        ];
+        for (source, expected) in cases {
+            let mut scanner = Scanner::new(source);
+            let tokens = scanner.scan_tokens().unwrap();
+            let mut parser = Parser::new(tokens);
+            let parsed_expr = parser.parse().unwrap();
+
+            assert_eq!(parsed_expr.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_index_assignment() {
+        let source = "xs[0] = ys[1] = 2";
+        let mut scanner = Scanner::new(source);
+        let tokens = scanner.scan_tokens().unwrap();
+        let mut parser = Parser::new(tokens);
+        let parsed_expr = parser.parse().unwrap();
+        let string_expr = parsed_expr.to_string();
+
+        assert_eq!(string_expr, "(= (index xs 0) (= (index ys 1) 2))");
+    }
+
+    #[test]
+    fn test_invalid_assignment_target() {
+        for source in ["1 = 2", "xs[1:2] = 3", "xs[] = 1"] {
+            let mut scanner = Scanner::new(source);
+            let tokens = scanner.scan_tokens().unwrap();
+            let mut parser = Parser::new(tokens);
+
+            assert!(parser.parse().is_err());
+        }
+    }
+
+    #[test]
+    fn test_map_literal() {
+        let source = "{1: true, nil: {}, k: [2]}[k]";
+        let mut scanner = Scanner::new(source);
+        let tokens = scanner.scan_tokens().unwrap();
+        let mut parser = Parser::new(tokens);
+        let parsed_expr = parser.parse().unwrap();
+        let string_expr = parsed_expr.to_string();
+
+        assert_eq!(
+            string_expr,
+            "(index (map (1 true) (nil (map)) (k (list 2))) k)"
+        );
+    }
+
+    #[test]
+    fn test_map_invalid_literals() {
+        for source in ["{1: 2,}", "{1 2}", "{1: }", "{1: 2"] {
+            let mut scanner = Scanner::new(source);
+            let tokens = scanner.scan_tokens().unwrap();
+            let mut parser = Parser::new(tokens);
+
+            assert!(parser.parse().is_err());
+        }
+    }
+
+    #[test]
+    fn test_in_operator() {
+        let source = "k in m == true";
+        let mut scanner = Scanner::new(source);
+        let tokens = scanner.scan_tokens().unwrap();
+        let mut parser = Parser::new(tokens);
+        let parsed_expr = parser.parse().unwrap();
+        let string_expr = parsed_expr.to_string();
+
+        assert_eq!(string_expr, "(== (in k m) true)");
+    }
+
+    #[test]
+    fn test_ranges() {
+        let cases = [
+            ("0..n + 1", "(.. 0 (+ n 1))"),
+            ("0..=10 step 2", "(..= 0 10 2)"),
+            ("0..n step step", "(.. 0 n step)"),
+            ("step(step: step)", "(call step step: step)"),
+            ("i in 0..len(xs)", "(in i (.. 0 (call len xs)))"),
+            ("xs[1..-1]", "(index xs (.. 1 (- 1)))"),
+        ];
+        for (source, expected) in cases {
+            let mut scanner = Scanner::new(source);
+            let tokens = scanner.scan_tokens().unwrap();
+            let mut parser = Parser::new(tokens);
+            let parsed_expr = parser.parse().unwrap();
+
+            assert_eq!(parsed_expr.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_chained_range() {
+        let source = "0..1..2";
+        let mut scanner = Scanner::new(source);
+        let tokens = scanner.scan_tokens().unwrap();
+        let mut parser = Parser::new(tokens);
+
+        assert!(parser.parse().is_err());
+    }
+
+    #[test]
+    fn test_spread() {
+        let cases = [
+            ("f(1, ...args)", "(call f 1 (... args))"),
+            ("[...a, 0, ...b]", "(list (... a) 0 (... b))"),
+            ("{...defaults, k: v}", "(map (... defaults) (k v))"),
+        ];
+        for (source, expected) in cases {
+            let mut scanner = Scanner::new(source);
+            let tokens = scanner.scan_tokens().unwrap();
+            let mut parser = Parser::new(tokens);
+            let parsed_expr = parser.parse().unwrap();
+
+            assert_eq!(parsed_expr.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_spread_outside_collection() {
+        for source in ["...xs", "(...xs)", "f(a: 1, ...xs)", "{...m: 1}"] {
+            let mut scanner = Scanner::new(source);
+            let tokens = scanner.scan_tokens().unwrap();
+            let mut parser = Parser::new(tokens);
+
+            assert!(parser.parse().is_err());
+        }
+    }
+
+    #[test]
+    fn test_empty_token_stream() {
+        let mut parser = Parser::new(vec![]);
+        assert!(parser.parse().is_err());
+
+        let tokens = vec![Token::new(LeftParen, "(".to_string(), None, 1)];
+        let mut parser = Parser::new(tokens);
+        assert!(parser.parse().is_err());
+    }
+
+    #[test]
+    fn test_literal_token_without_value() {
+        let tokens = vec![
+            Token::new(Number, "1".to_string(), None, 1),
+            Token::new(Eof, "".to_string(), None, 1),
+        ];
+        let mut parser = Parser::new(tokens);
+
+        assert!(parser.parse().is_err());
+    }
+
+    #[test]
+    fn test_deep_nesting() {
+        let depth = MAX_NESTING - 1;
+        let source = format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        let mut scanner = Scanner::new(&source);
+        let tokens = scanner.scan_tokens().unwrap();
+        let mut parser = Parser::new(tokens);
+        assert!(parser.parse().is_ok());
+
+        let source = format!("1{}", " + 1".repeat(MAX_AST_DEPTH - 1));
+        let mut scanner = Scanner::new(&source);
+        let tokens = scanner.scan_tokens().unwrap();
+        let mut parser = Parser::new(tokens);
+        let parsed_expr = parser.parse().unwrap();
+        assert!(parsed_expr.to_string().starts_with("(+ (+ "));
+
+        let mut compounded = "1".to_string();
+        for _ in 0..20 {
+            compounded = format!("({}{})", compounded, " + 1".repeat(20));
+        }
+
+        for source in [
+            format!("{}1{}", "(".repeat(100_000), ")".repeat(100_000)),
+            format!("{}1", "-".repeat(100_000)),
+            format!("{}1", "[".repeat(100_000)),
+            "xs[0] = ".repeat(100_000),
+            format!("f{}", "()".repeat(50_000)),
+            format!("1{}", " + 1".repeat(100_000)),
+            format!("x{}", ".y".repeat(100_000)),
+            format!("x{}", "[0]".repeat(100_000)),
+            format!("1{}", " + 1".repeat(MAX_AST_DEPTH)),
+            compounded,
+        ] {
+            let mut scanner = Scanner::new(&source);
+            let tokens = scanner.scan_tokens().unwrap();
+            let mut parser = Parser::new(tokens);
+            assert!(parser.parse().is_err());
+        }
+    }
+
+    #[test]
+    fn test_long_parameter_and_argument_lists() {
+        let count = 50_000;
+        let names: Vec<String> = (0..count).map(|i| format!("x{}", i)).collect();
+        let sources = [
+            format!("({}) => 1", names.join(", ")),
+            format!("f({})", names.join(": 1, ") + ": 1"),
+        ];
+        for source in sources {
+            let start = std::time::Instant::now();
+            let mut scanner = Scanner::new(&source);
+            let tokens = scanner.scan_tokens().unwrap();
+            let mut parser = Parser::new(tokens);
+
+            assert!(parser.parse().is_ok());
+            assert!(start.elapsed() < std::time::Duration::from_secs(5));
+        }
+    }
+
+    #[test]
+    fn test_method_calls() {
+        let cases = [
+            ("\"abc\".upper()", "(call (get abc upper))"),
+            (
+                "s.split(x)[0].trim()",
+                "(call (get (index (call (get s split) x) 0) trim))",
+            ),
+            ("1.5.floor", "(get 1.5 floor)"),
+        ];
+        for (source, expected) in cases {
+            let mut scanner = Scanner::new(source);
+            let tokens = scanner.scan_tokens().unwrap();
+            let mut parser = Parser::new(tokens);
+            let parsed_expr = parser.parse().unwrap();
+
+            assert_eq!(parsed_expr.to_string(), expected);
+        }
+
+        let mut scanner = Scanner::new("s.1");
+        let tokens = scanner.scan_tokens().unwrap();
+        let mut parser = Parser::new(tokens);
+        assert!(parser.parse().is_err());
+    }
 }
@@ -2,12 +2,10 @@ use std::collections::HashMap;
 use std::string::String;
 
 fn is_digit(ch: char) -> bool {
-    let uch = ch as u8;
-    uch >= '0' as u8 && uch <= '9' as u8
+    ch.is_ascii_digit()
 }
 fn is_alpha(ch: char) -> bool {
-    let uch = ch as u8;
-    (uch >= 'a' as u8 && uch <= 'z' as u8) || (uch >= 'A' as u8 && uch <= 'Z' as u8) || (ch == '_')
+    ch.is_ascii_alphabetic() || ch == '_'
 }
 
 fn is_alpha_numeric(ch: char) -> bool {
@@ -17,26 +15,40 @@ fn is_alpha_numeric(ch: char) -> bool {
 fn get_keywords_hashmap() -> HashMap<&'static str, TokenType> {
     HashMap::from([
         ("and", And),
+        ("break", Break),
+        ("case", Case),
+        ("catch", Catch),
         ("class", Class),
+        ("const", Const),
+        ("continue", Continue),
+        ("defer", Defer),
+        ("do", Do),
         ("else", Else),
+        ("export", Export),
         ("false", False),
+        ("finally", Finally),
         ("for", For),
         ("fun", Fun),
         ("if", If),
+        ("import", Import),
+        ("in", In),
+        ("match", Match),
         ("nil", Nil),
         ("or", Or),
         ("print", Print),
         ("return", Return),
         ("super", Super),
         ("this", This),
+        ("throw", Throw),
         ("true", True),
+        ("try", Try),
         ("var", Var),
         ("while", While),
     ])
 }
 
 pub struct Scanner {
-    source: String,
+    source: Vec<char>,
     tokens: Vec<Token>,
     start: usize,
     current: usize,
@@ -47,7 +59,7 @@ pub struct Scanner {
 impl Scanner {
     pub fn new(source: &str) -> Self {
         Self {
-            source: source.to_string(),
+            source: source.chars().collect(),
             tokens: vec![],
             start: 0,
             current: 0,
@@ -70,11 +82,11 @@ impl Scanner {
             literal: None,
             line_number: self.line,
         });
-        if errors.len() > 0 {
+        if !errors.is_empty() {
             let mut joined = "".to_string();
             for error in errors {
                 joined.push_str(&error);
-                joined.push_str("\n");
+                joined.push('\n');
             }
             return Err(joined);
         }
@@ -91,8 +103,24 @@ impl Scanner {
             ')' => self.add_token(RightParen),
             '{' => self.add_token(LeftBrace),
             '}' => self.add_token(RightBrace),
+            '[' => self.add_token(LeftBracket),
+            ']' => self.add_token(RightBracket),
             ',' => self.add_token(Comma),
-            '.' => self.add_token(Dot),
+            ':' => self.add_token(Colon),
+            '.' => {
+                let token = if self.char_match('.') {
+                    if self.char_match('.') {
+                        Ellipsis
+                    } else if self.char_match('=') {
+                        DotDotEqual
+                    } else {
+                        DotDot
+                    }
+                } else {
+                    Dot
+                };
+                self.add_token(token)
+            }
             '-' => self.add_token(Minus),
             '+' => self.add_token(Plus),
             ';' => self.add_token(Semicolon),
@@ -108,6 +136,8 @@ impl Scanner {
             '=' => {
                 let token = if self.char_match('=') {
                     EqualEqual
+                } else if self.char_match('>') {
+                    Arrow
                 } else {
                     Equal
                 };
@@ -162,8 +192,8 @@ impl Scanner {
             self.advance();
         }
 
-        let substring = &self.source[self.start..self.current];
-        if let Some(&t_type) = self.keywords.get(substring) {
+        let substring = self.substring(self.start, self.current);
+        if let Some(&t_type) = self.keywords.get(substring.as_str()) {
             self.add_token(t_type);
         } else {
             self.add_token(Identifier);
@@ -180,7 +210,7 @@ impl Scanner {
                 self.advance();
             }
         }
-        let substring = &self.source[self.start..self.current];
+        let substring = self.substring(self.start, self.current);
         let value = substring.parse::<f64>();
         match value {
             Ok(value) => self.add_token_lit(Number, Some(FValue(value))),
@@ -189,12 +219,11 @@ impl Scanner {
 
         Ok(())
     }
-    fn peek_next(self: &Self) -> char {
-        if self.current + 1 >= self.source.len() {
-            return '\0';
+    fn peek_next(&self) -> char {
+        match self.source.get(self.current + 1) {
+            Some(&c) => c,
+            None => '\0',
         }
-
-        self.source.chars().nth(self.current + 1).unwrap()
     }
 
     fn string(&mut self) -> Result<(), String> {
@@ -208,47 +237,51 @@ impl Scanner {
             return Err("Unterminated string".to_string());
         }
         self.advance();
-        let value = self.source[self.start + 1..self.current - 1].to_string();
+        let value = self.substring(self.start + 1, self.current - 1);
         self.add_token_lit(StringLit, Some(StringValue(value)));
         Ok(())
     }
 
-    fn peek(self: &Self) -> char {
-        if self.is_at_end() {
-            return '\0';
+    fn peek(&self) -> char {
+        match self.source.get(self.current) {
+            Some(&c) => c,
+            None => '\0',
         }
-        self.source.chars().nth(self.current).unwrap()
     }
 
     fn char_match(&mut self, ch: char) -> bool {
         if self.is_at_end() {
             return false;
         }
-        if self.source.chars().nth(self.current).unwrap() != ch {
-            return false;
+        if self.source[self.current] != ch {
+            false
         } else {
             self.current += 1;
-            return true;
+            true
         }
     }
 
     fn advance(&mut self) -> char {
-        let c = self.source.chars().nth(self.current).unwrap();
+        let c = self.peek();
         self.current += 1;
 
         c
     }
 
+    fn substring(&self, start: usize, end: usize) -> String {
+        self.source[start..end].iter().collect()
+    }
+
     fn add_token(&mut self, token_type: TokenType) {
         self.add_token_lit(token_type, None);
     }
 
     fn add_token_lit(&mut self, token_type: TokenType, literal: Option<LiteralValue>) {
-        let text: String = self.source[self.start..self.current].to_string();
+        let text = self.substring(self.start, self.current);
         self.tokens.push(Token {
-            token_type: token_type,
+            token_type,
             lexeme: text,
-            literal: literal,
+            literal,
             line_number: self.line,
         });
     }
@@ -262,7 +295,10 @@ pub enum TokenType {
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
     Comma,
+    Colon,
     Dot,
     Minus,
     Plus,
@@ -279,6 +315,10 @@ pub enum TokenType {
     GreaterEqual,
     Less,
     LessEqual,
+    Arrow,
+    DotDot,
+    DotDotEqual,
+    Ellipsis,
 
     // Literals
     Identifier,
@@ -287,19 +327,33 @@ pub enum TokenType {
 
     // Keywords
     And,
+    Break,
+    Case,
+    Catch,
     Class,
+    Const,
+    Continue,
+    Defer,
+    Do,
     Else,
+    Export,
     False,
+    Finally,
     Fun,
     For,
     If,
+    Import,
+    In,
+    Match,
     Nil,
     Or,
     Print,
     Return,
     Super,
     This,
+    Throw,
     True,
+    Try,
     Var,
     While,
 
@@ -347,9 +401,11 @@ impl Token {
             line_number,
         }
     }
+}
 
-    pub fn to_string(self: &Self) -> String {
-        format!("{} {} {:?}", self.token_type, self.lexeme, self.literal)
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} {} {:?}", self.token_type, self.lexeme, self.literal)
     }
 }
 
@@ -419,9 +475,8 @@ mod tests {
         let source = r#""ABC"#;
         let mut scanner = Scanner::new(source);
         let scan_tokens = scanner.scan_tokens();
-        match scan_tokens {
-            Ok(_) => panic!("shoud have failed"),
-            Err(_) => (),
+        if scan_tokens.is_ok() {
+            panic!("shoud have failed");
         }
     }
 
@@ -454,4 +509,145 @@ mod tests {
         }
         assert_eq!(scanner.tokens[3].token_type, Eof);
     }
+
+    #[test]
+    fn handle_loop_control_keywords() {
+        let source = "outer: while (true) { break outer; continue; }";
+        let mut scanner = Scanner::new(source);
+        scanner.scan_tokens().unwrap();
+        assert_eq!(scanner.tokens[0].token_type, Identifier);
+        assert_eq!(scanner.tokens[1].token_type, Colon);
+        assert_eq!(scanner.tokens[7].token_type, Break);
+        assert_eq!(scanner.tokens[8].token_type, Identifier);
+        assert_eq!(scanner.tokens[10].token_type, Continue);
+    }
+
+    #[test]
+    fn handle_match_keywords() {
+        let source = "match (x) { case 1 if y: }";
+        let mut scanner = Scanner::new(source);
+        scanner.scan_tokens().unwrap();
+        assert_eq!(scanner.tokens[0].token_type, Match);
+        assert_eq!(scanner.tokens[5].token_type, Case);
+        assert_eq!(scanner.tokens[7].token_type, If);
+        assert_eq!(scanner.tokens[9].token_type, Colon);
+    }
+
+    #[test]
+    fn handle_do_and_defer_keywords() {
+        let source = "do { defer close(); } while (x);";
+        let mut scanner = Scanner::new(source);
+        scanner.scan_tokens().unwrap();
+        assert_eq!(scanner.tokens[0].token_type, Do);
+        assert_eq!(scanner.tokens[2].token_type, Defer);
+        assert_eq!(scanner.tokens[3].token_type, Identifier);
+        assert_eq!(scanner.tokens[8].token_type, While);
+    }
+
+    #[test]
+    fn handle_arrow_token() {
+        let source = "(a) => a == b = c";
+        let mut scanner = Scanner::new(source);
+        scanner.scan_tokens().unwrap();
+        assert_eq!(scanner.tokens[3].token_type, Arrow);
+        assert_eq!(scanner.tokens[5].token_type, EqualEqual);
+        assert_eq!(scanner.tokens[7].token_type, Equal);
+    }
+
+    #[test]
+    fn handle_ellipsis() {
+        let source = "a.b ...c";
+        let mut scanner = Scanner::new(source);
+        scanner.scan_tokens().unwrap();
+        assert_eq!(scanner.tokens.len(), 6);
+        assert_eq!(scanner.tokens[1].token_type, Dot);
+        assert_eq!(scanner.tokens[3].token_type, Ellipsis);
+        assert_eq!(scanner.tokens[4].token_type, Identifier);
+    }
+
+    #[test]
+    fn handle_brackets() {
+        let source = "xs[1:][0]";
+        let mut scanner = Scanner::new(source);
+        scanner.scan_tokens().unwrap();
+        assert_eq!(scanner.tokens.len(), 9);
+        assert_eq!(scanner.tokens[1].token_type, LeftBracket);
+        assert_eq!(scanner.tokens[3].token_type, Colon);
+        assert_eq!(scanner.tokens[4].token_type, RightBracket);
+        assert_eq!(scanner.tokens[5].token_type, LeftBracket);
+        assert_eq!(scanner.tokens[7].token_type, RightBracket);
+    }
+
+    #[test]
+    fn handle_in_keyword() {
+        let source = "key in index";
+        let mut scanner = Scanner::new(source);
+        scanner.scan_tokens().unwrap();
+        assert_eq!(scanner.tokens[0].token_type, Identifier);
+        assert_eq!(scanner.tokens[1].token_type, In);
+        assert_eq!(scanner.tokens[2].token_type, Identifier);
+    }
+
+    #[test]
+    fn handle_range_tokens() {
+        let source = "0..10 step 2 1..=n 1.5..2";
+        let mut scanner = Scanner::new(source);
+        scanner.scan_tokens().unwrap();
+        assert_eq!(scanner.tokens.len(), 12);
+        assert_eq!(scanner.tokens[1].token_type, DotDot);
+        assert_eq!(scanner.tokens[3].token_type, Identifier);
+        assert_eq!(scanner.tokens[6].token_type, DotDotEqual);
+        match scanner.tokens[8].literal {
+            Some(FValue(val)) => assert_eq!(val, 1.5),
+            _ => panic!("incorect literal type"),
+        }
+        assert_eq!(scanner.tokens[9].token_type, DotDot);
+    }
+
+    #[test]
+    fn handle_const_keyword() {
+        let source = "const constant = 1;";
+        let mut scanner = Scanner::new(source);
+        scanner.scan_tokens().unwrap();
+        assert_eq!(scanner.tokens[0].token_type, Const);
+        assert_eq!(scanner.tokens[1].token_type, Identifier);
+        assert_eq!(scanner.tokens[1].lexeme, "constant");
+    }
+
+    #[test]
+    fn handle_exception_keywords() {
+        let source = "try {} catch (e) { throw e; } finally {}";
+        let mut scanner = Scanner::new(source);
+        scanner.scan_tokens().unwrap();
+        assert_eq!(scanner.tokens[0].token_type, Try);
+        assert_eq!(scanner.tokens[3].token_type, Catch);
+        assert_eq!(scanner.tokens[8].token_type, Throw);
+        assert_eq!(scanner.tokens[12].token_type, Finally);
+    }
+
+    #[test]
+    fn handle_non_ascii_source() {
+        let source = "\"h\u{e9}llo \u{1F600}\" + x";
+        let mut scanner = Scanner::new(source);
+        scanner.scan_tokens().unwrap();
+        assert_eq!(scanner.tokens.len(), 4);
+        match scanner.tokens[0].literal.as_ref().unwrap() {
+            StringValue(val) => assert_eq!(val, "h\u{e9}llo \u{1F600}"),
+            _ => panic!("incorect literal type"),
+        }
+
+        let mut scanner = Scanner::new("caf\u{e9} = 1");
+        assert!(scanner.scan_tokens().is_err());
+    }
+
+    #[test]
+    fn handle_module_keywords() {
+        let source = "import \"util.lox\" as u; export var x;";
+        let mut scanner = Scanner::new(source);
+        scanner.scan_tokens().unwrap();
+        assert_eq!(scanner.tokens[0].token_type, Import);
+        assert_eq!(scanner.tokens[1].token_type, StringLit);
+        assert_eq!(scanner.tokens[2].token_type, Identifier);
+        assert_eq!(scanner.tokens[5].token_type, Export);
+    }
 }